@@ -1,57 +1,112 @@
-use crate::token::{TokenKind, lookup_identifier, Token, Span};
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::token::{TokenKind, lookup_identifier, Token, Span, Diagnostic, LexErrorKind};
 
 pub mod token;
 
 pub struct Lexer<'a> {
     input: &'a str,
+    chars: Peekable<Chars<'a>>,
     position: usize,
     read_position: usize,
     ch: char,
+    line: usize,
+    column: usize,
+    diagnostics: Vec<Diagnostic>,
+    emit_comments: bool,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Self {
+        Self::new_with(input, false)
+    }
+
+    /// Like [`Lexer::new`], but `next_token` surfaces `//` and `/* */` comments
+    /// as `TokenKind::COMMENT` tokens instead of skipping over them, for
+    /// tooling (formatters, an LSP) that needs to see them.
+    pub fn with_comments(input: &'a str) -> Self {
+        Self::new_with(input, true)
+    }
+
+    fn new_with(input: &'a str, emit_comments: bool) -> Self {
         let mut l = Lexer {
             input,
+            chars: input.chars().peekable(),
             position: 0,
             read_position: 0,
             ch: 0 as char,
+            line: 1,
+            column: 0,
+            diagnostics: Vec::new(),
+            emit_comments,
         };
 
         l.read_char();
-        return l;
+        l
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
     }
 
     fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = 0 as char
-        } else {
-            if let Some(ch) = self.input.chars().nth(self.read_position) {
+        self.position = self.read_position;
+        match self.chars.next() {
+            Some(ch) => {
                 self.ch = ch;
-            } else {
-                panic!("read out of range")
+                self.read_position += ch.len_utf8();
+                if ch == '\n' {
+                    self.line += 1;
+                    self.column = 0;
+                } else {
+                    self.column += 1;
+                }
             }
+            None => self.ch = 0 as char,
         }
+    }
 
-        self.position = self.read_position;
-        self.read_position += 1;
+    /// The 1-based (line, column) of the current `self.ch`, for stamping onto the span of
+    /// the token about to be read. Column counts characters, not bytes.
+    fn pos(&self) -> (usize, usize) {
+        if self.position >= self.input.len() {
+            // At EOF no character was read, so the position is one past the last one.
+            return (self.line, self.column + 1);
+        }
+        (self.line, self.column)
     }
 
-    fn peek_char(&self) -> char {
-        if self.read_position >= self.input.len() {
-            0 as char
-        } else {
-            if let Some(ch) = self.input.chars().nth(self.read_position) {
-                ch
+    fn peek_char(&mut self) -> char {
+        *self.chars.peek().unwrap_or(&(0 as char))
+    }
+
+    pub fn next_token(&mut self) -> Token<'a> {
+        self.skip_whitespace();
+
+        loop {
+            let (line, column) = self.pos();
+
+            if self.ch == '/' && self.peek_char() == '/' {
+                let (start, end, text) = self.read_line_comment();
+                if self.emit_comments {
+                    return Token { span: Span { start, end, line, column }, kind: TokenKind::COMMENT(text) };
+                }
+            } else if self.emit_comments && self.ch == '/' && self.peek_char() == '*' {
+                // Only recognized when comments are surfaced: unlike `//`, `/*` collides
+                // with the valid `SLASH ASTERISK` token sequence (e.g. `a/*b`), so the
+                // default lexer must not treat it as a comment and break that parse.
+                let (start, end, text) = self.read_block_comment(line, column);
+                return Token { span: Span { start, end, line, column }, kind: TokenKind::COMMENT(text) };
             } else {
-                panic!("read out of range")
+                break;
             }
+
+            self.skip_whitespace();
         }
-    }
 
-    pub fn next_token(&mut self) -> Token {
-        // println!("self ch {}, position {} read_position {}", self.ch, self.position, self.read_position);
-        self.skip_whitespace();
+        let (line, column) = self.pos();
+        let start = self.position;
         let t = match self.ch {
             '=' => {
                 if self.peek_char() == '=' {
@@ -86,24 +141,30 @@ impl<'a> Lexer<'a> {
             ']' => TokenKind::RBRACKET,
             '\u{0}' => TokenKind::EOF,
             '"' => {
-                let (start, end, string) = self.read_string();
-                return Token { span: Span {start, end},  kind: TokenKind::STRING(string) };
+                let (start, end, string) = self.read_string(line, column);
+                return Token { span: Span {start, end, line, column},  kind: TokenKind::STRING(string) };
             },
             _ => {
                 if is_letter(self.ch) {
                     let (start, end, identifier) = self.read_identifier();
-                    return Token { span: Span {start, end}, kind: lookup_identifier(&identifier) };
+                    return Token { span: Span {start, end, line, column}, kind: lookup_identifier(identifier) };
                 } else if is_digit(self.ch) {
-                    let (start, end, num) = self.read_number();
-                    return Token { span: Span {start, end}, kind: TokenKind::INT(num) };
+                    let (start, end, kind) = self.read_number(line, column);
+                    return Token { span: Span {start, end, line, column}, kind };
                 } else {
                     TokenKind::ILLEGAL
                 }
             }
         };
 
+        let illegal_char = if t == TokenKind::ILLEGAL { Some(self.ch) } else { None };
+        let end = self.read_position;
         self.read_char();
-        return Token { span: Span {start: self.position - 1, end: self.read_position - 1, }, kind: t };
+        let token = Token { span: Span { start, end, line, column }, kind: t };
+        if let Some(ch) = illegal_char {
+            self.diagnostics.push(Diagnostic { span: token.span.clone(), kind: LexErrorKind::UnexpectedCharacter(ch) });
+        }
+        token
     }
 
     fn skip_whitespace(&mut self) {
@@ -112,28 +173,148 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn read_identifier(&mut self) -> (usize, usize, String) {
+    fn read_line_comment(&mut self) -> (usize, usize, &'a str) {
+        let pos = self.position;
+        while self.ch != '\n' && self.ch != '\u{0}' {
+            self.read_char();
+        }
+
+        (pos, self.position, &self.input[pos..self.position])
+    }
+
+    fn read_block_comment(&mut self, line: usize, column: usize) -> (usize, usize, &'a str) {
+        let pos = self.position;
+        self.read_char(); // consume '/'
+        self.read_char(); // consume '*'
+
+        loop {
+            if self.ch == '\u{0}' {
+                self.diagnostics.push(Diagnostic {
+                    span: Span { start: pos, end: self.position, line, column },
+                    kind: LexErrorKind::UnterminatedComment,
+                });
+                break;
+            }
+
+            if self.ch == '*' && self.peek_char() == '/' {
+                self.read_char();
+                self.read_char();
+                break;
+            }
+
+            self.read_char();
+        }
+
+        (pos, self.position, &self.input[pos..self.position])
+    }
+
+    fn read_identifier(&mut self) -> (usize, usize, &'a str) {
         let pos = self.position;
         while is_letter(self.ch) {
             self.read_char();
         }
 
-        let x = self.input[pos..self.position].to_string();
-        return (pos, self.position, x)
+        let x = &self.input[pos..self.position];
+        (pos, self.position, x)
     }
 
-    fn read_number(&mut self) -> (usize, usize, i64) {
+    fn read_number(&mut self, line: usize, column: usize) -> (usize, usize, TokenKind<'a>) {
         let pos = self.position;
-        while is_digit(self.ch) {
+
+        if self.ch == '0' && matches!(self.peek_char(), 'x' | 'X' | 'o' | 'O' | 'b' | 'B') {
+            return self.read_radix_number(pos, line, column);
+        }
+
+        let mut is_float = false;
+
+        while is_digit(self.ch) || self.ch == '_' {
             self.read_char();
         }
 
-        let x = self.input[pos..self.position].parse().unwrap();
+        if self.ch == '.' && is_digit(self.peek_char()) {
+            is_float = true;
+            self.read_char();
+            while is_digit(self.ch) || self.ch == '_' {
+                self.read_char();
+            }
+        }
+
+        if self.ch == 'e' || self.ch == 'E' {
+            let mut ahead = self.chars.clone();
+            let mut ahead_ch = ahead.next();
+            let mut has_sign = false;
+            if ahead_ch == Some('+') || ahead_ch == Some('-') {
+                has_sign = true;
+                ahead_ch = ahead.next();
+            }
+            if ahead_ch.is_some_and(is_digit) {
+                is_float = true;
+                self.read_char();
+                if has_sign {
+                    self.read_char();
+                }
+                while is_digit(self.ch) || self.ch == '_' {
+                    self.read_char();
+                }
+            }
+        }
+
+        let end = self.position;
+        let cleaned = self.input[pos..end].replace('_', "");
+
+        let kind = if is_float {
+            match cleaned.parse::<f64>() {
+                Ok(f) => TokenKind::FLOAT(f),
+                Err(_) => self.invalid_number(pos, end, line, column),
+            }
+        } else {
+            match cleaned.parse::<i64>() {
+                Ok(n) => TokenKind::INT(n),
+                Err(_) => self.invalid_number(pos, end, line, column),
+            }
+        };
+
+        (pos, end, kind)
+    }
+
+    fn read_radix_number(&mut self, pos: usize, line: usize, column: usize) -> (usize, usize, TokenKind<'a>) {
+        let radix: u32 = match self.peek_char() {
+            'x' | 'X' => 16,
+            'o' | 'O' => 8,
+            'b' | 'B' => 2,
+            _ => unreachable!(),
+        };
+        self.read_char(); // consume '0'
+        self.read_char(); // consume the radix prefix letter
+
+        while self.ch.is_digit(radix) || self.ch == '_' {
+            self.read_char();
+        }
+
+        let end = self.position;
+        let digits = self.input[pos + 2..end].replace('_', "");
+
+        let kind = if digits.is_empty() {
+            self.invalid_number(pos, end, line, column)
+        } else {
+            match i64::from_str_radix(&digits, radix) {
+                Ok(n) => TokenKind::INT(n),
+                Err(_) => self.invalid_number(pos, end, line, column),
+            }
+        };
+
+        (pos, end, kind)
+    }
 
-        return (pos, self.position, x)
+    fn invalid_number(&mut self, start: usize, end: usize, line: usize, column: usize) -> TokenKind<'a> {
+        self.diagnostics.push(Diagnostic {
+            span: Span { start, end, line, column },
+            kind: LexErrorKind::InvalidNumericLiteral,
+        });
+        TokenKind::ILLEGAL
     }
 
-    fn read_string(&mut self) -> (usize, usize, String) {
+    fn read_string(&mut self, line: usize, column: usize) -> (usize, usize, &'a str) {
         let pos = self.position + 1;
         loop {
             self.read_char();
@@ -141,14 +322,19 @@ impl<'a> Lexer<'a> {
                 break
             }
         }
-        
-        let x = self.input[pos..self.position].to_string();
+
+        let x = &self.input[pos..self.position];
 
         // consume the end "
-        if self.ch == '"'{
+        if self.ch == '"' {
             self.read_char();
+        } else {
+            self.diagnostics.push(Diagnostic {
+                span: Span { start: pos - 1, end: self.position, line, column },
+                kind: LexErrorKind::UnclosedStringLiteral,
+            });
         }
-        return (pos - 1, self.position, x)
+        (pos - 1, self.position, x)
     }
 }
 
@@ -157,7 +343,7 @@ fn is_letter(c: char) -> bool {
 }
 
 fn is_digit(c: char) -> bool {
-    c >= '0' && c <= '9'
+    c.is_ascii_digit()
 }
 
 #[cfg(test)]
@@ -167,7 +353,7 @@ mod tests {
     use super::*;
     use insta::assert_debug_snapshot;
 
-    fn test_token_set(l: &mut Lexer) -> Vec<Token> {
+    fn test_token_set<'a>(l: &mut Lexer<'a>) -> Vec<Token<'a>> {
         let mut token_vs: Vec<Token> = vec![];
         loop {
             let t = l.next_token();
@@ -262,4 +448,38 @@ if (5 < 10) {
 
         assert_debug_snapshot!(token_vs)
     }
+
+    #[test]
+    fn test_lexer_unterminated_string() {
+        let mut l = Lexer::new(r#""a"#);
+        let token_vs = test_token_set(&mut l);
+
+        assert_debug_snapshot!(token_vs);
+        assert_debug_snapshot!(l.diagnostics());
+    }
+
+    #[test]
+    fn test_lexer_numeric_literals() {
+        let mut l = Lexer::new("3.14 0xFF 99999999999999999999");
+        let token_vs = test_token_set(&mut l);
+
+        assert_debug_snapshot!(token_vs);
+        assert_debug_snapshot!(l.diagnostics());
+    }
+
+    #[test]
+    fn test_lexer_line_and_column() {
+        let mut l = Lexer::new("let x = 1;\nlet y = 2;");
+        let token_vs = test_token_set(&mut l);
+
+        assert_debug_snapshot!(token_vs);
+    }
+
+    #[test]
+    fn test_lexer_comments() {
+        let mut l = Lexer::with_comments("// hi\nlet x = 1; /* block */ x");
+        let token_vs = test_token_set(&mut l);
+
+        assert_debug_snapshot!(token_vs);
+    }
 }
@@ -0,0 +1,188 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    /// 1-based line the span starts on.
+    pub line: usize,
+    /// 1-based character (not byte) column within that line the span starts on.
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token<'a> {
+    pub span: Span,
+    pub kind: TokenKind<'a>,
+}
+
+impl<'a> Token<'a> {
+    /// Detaches this token from the input it was lexed from, allocating owned
+    /// copies of any borrowed text. Useful for callers (e.g. a REPL history)
+    /// that need to keep tokens around after the source `&str` goes away.
+    pub fn to_owned(&self) -> OwnedToken {
+        OwnedToken {
+            span: self.span.clone(),
+            kind: self.kind.to_owned_kind(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind<'a> {
+    ILLEGAL,
+    EOF,
+
+    IDENT(&'a str),
+    INT(i64),
+    FLOAT(f64),
+    STRING(&'a str),
+    COMMENT(&'a str),
+
+    ASSIGN,
+    PLUS,
+    MINUS,
+    BANG,
+    ASTERISK,
+    SLASH,
+
+    LT,
+    GT,
+    EQ,
+    NotEq,
+
+    COMMA,
+    SEMICOLON,
+    COLON,
+
+    LPAREN,
+    RPAREN,
+    LBRACE,
+    RBRACE,
+    LBRACKET,
+    RBRACKET,
+
+    FUNCTION,
+    LET,
+    TRUE,
+    FALSE,
+    IF,
+    ELSE,
+    RETURN,
+}
+
+impl<'a> TokenKind<'a> {
+    pub fn to_owned_kind(&self) -> OwnedTokenKind {
+        match self {
+            TokenKind::ILLEGAL => OwnedTokenKind::ILLEGAL,
+            TokenKind::EOF => OwnedTokenKind::EOF,
+            TokenKind::IDENT(s) => OwnedTokenKind::IDENT(s.to_string()),
+            TokenKind::INT(n) => OwnedTokenKind::INT(*n),
+            TokenKind::FLOAT(f) => OwnedTokenKind::FLOAT(*f),
+            TokenKind::STRING(s) => OwnedTokenKind::STRING(s.to_string()),
+            TokenKind::COMMENT(s) => OwnedTokenKind::COMMENT(s.to_string()),
+            TokenKind::ASSIGN => OwnedTokenKind::ASSIGN,
+            TokenKind::PLUS => OwnedTokenKind::PLUS,
+            TokenKind::MINUS => OwnedTokenKind::MINUS,
+            TokenKind::BANG => OwnedTokenKind::BANG,
+            TokenKind::ASTERISK => OwnedTokenKind::ASTERISK,
+            TokenKind::SLASH => OwnedTokenKind::SLASH,
+            TokenKind::LT => OwnedTokenKind::LT,
+            TokenKind::GT => OwnedTokenKind::GT,
+            TokenKind::EQ => OwnedTokenKind::EQ,
+            TokenKind::NotEq => OwnedTokenKind::NotEq,
+            TokenKind::COMMA => OwnedTokenKind::COMMA,
+            TokenKind::SEMICOLON => OwnedTokenKind::SEMICOLON,
+            TokenKind::COLON => OwnedTokenKind::COLON,
+            TokenKind::LPAREN => OwnedTokenKind::LPAREN,
+            TokenKind::RPAREN => OwnedTokenKind::RPAREN,
+            TokenKind::LBRACE => OwnedTokenKind::LBRACE,
+            TokenKind::RBRACE => OwnedTokenKind::RBRACE,
+            TokenKind::LBRACKET => OwnedTokenKind::LBRACKET,
+            TokenKind::RBRACKET => OwnedTokenKind::RBRACKET,
+            TokenKind::FUNCTION => OwnedTokenKind::FUNCTION,
+            TokenKind::LET => OwnedTokenKind::LET,
+            TokenKind::TRUE => OwnedTokenKind::TRUE,
+            TokenKind::FALSE => OwnedTokenKind::FALSE,
+            TokenKind::IF => OwnedTokenKind::IF,
+            TokenKind::ELSE => OwnedTokenKind::ELSE,
+            TokenKind::RETURN => OwnedTokenKind::RETURN,
+        }
+    }
+}
+
+/// `'static` mirror of [`Token`] for callers that need to keep tokens around
+/// after the source `&str` has gone out of scope.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedToken {
+    pub span: Span,
+    pub kind: OwnedTokenKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedTokenKind {
+    ILLEGAL,
+    EOF,
+
+    IDENT(String),
+    INT(i64),
+    FLOAT(f64),
+    STRING(String),
+    COMMENT(String),
+
+    ASSIGN,
+    PLUS,
+    MINUS,
+    BANG,
+    ASTERISK,
+    SLASH,
+
+    LT,
+    GT,
+    EQ,
+    NotEq,
+
+    COMMA,
+    SEMICOLON,
+    COLON,
+
+    LPAREN,
+    RPAREN,
+    LBRACE,
+    RBRACE,
+    LBRACKET,
+    RBRACKET,
+
+    FUNCTION,
+    LET,
+    TRUE,
+    FALSE,
+    IF,
+    ELSE,
+    RETURN,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexErrorKind {
+    UnexpectedCharacter(char),
+    UnclosedStringLiteral,
+    InvalidNumericLiteral,
+    UnterminatedComment,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub kind: LexErrorKind,
+}
+
+pub fn lookup_identifier(ident: &str) -> TokenKind<'_> {
+    match ident {
+        "fn" => TokenKind::FUNCTION,
+        "let" => TokenKind::LET,
+        "true" => TokenKind::TRUE,
+        "false" => TokenKind::FALSE,
+        "if" => TokenKind::IF,
+        "else" => TokenKind::ELSE,
+        "return" => TokenKind::RETURN,
+        _ => TokenKind::IDENT(ident),
+    }
+}